@@ -0,0 +1,206 @@
+//! Track frame pacing and presentation latency.
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// A correlation anchor mapping a single instant on the CPU clock to the
+/// corresponding instant on wgpu's presentation clock.
+///
+/// A [`wgpu::PresentationTimestamp`] is only meaningful relative to another
+/// reading from the same clock—it can't be compared against an [`Instant`]
+/// directly. Capturing a CPU [`Instant`] and an
+/// [`Adapter::get_presentation_timestamp`] reading back-to-back gives a
+/// reference point that lets [`FrameTimings::record`] translate a later
+/// presentation timestamp back into CPU-clock terms, which is what makes a
+/// real submit→present latency measurement possible.
+///
+/// [`Adapter::get_presentation_timestamp`]: wgpu::Adapter::get_presentation_timestamp
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockCorrelation {
+    cpu: Instant,
+    presentation: wgpu::PresentationTimestamp,
+}
+
+impl ClockCorrelation {
+    /// Captures a new correlation anchor from the current CPU instant and
+    /// the adapter's current presentation-clock reading.
+    ///
+    /// The two clocks can drift apart over time, so recapture periodically
+    /// (e.g. once a second) rather than reusing a single anchor for the
+    /// lifetime of the renderer.
+    pub fn capture(adapter: &wgpu::Adapter) -> Self {
+        Self {
+            cpu: Instant::now(),
+            presentation: adapter.get_presentation_timestamp(),
+        }
+    }
+
+    fn to_cpu_instant(self, presentation_timestamp: wgpu::PresentationTimestamp) -> Instant {
+        let delta_ns = presentation_timestamp.0 as i128 - self.presentation.0 as i128;
+
+        if delta_ns >= 0 {
+            self.cpu + Duration::from_nanos(delta_ns as u64)
+        } else {
+            self.cpu - Duration::from_nanos((-delta_ns) as u64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod clock_correlation_tests {
+    use super::*;
+
+    #[test]
+    fn translates_a_later_presentation_timestamp_forward() {
+        let cpu = Instant::now();
+        let correlation = ClockCorrelation {
+            cpu,
+            presentation: wgpu::PresentationTimestamp(1_000),
+        };
+
+        let translated = correlation.to_cpu_instant(wgpu::PresentationTimestamp(1_500));
+
+        assert_eq!(translated, cpu + Duration::from_nanos(500));
+    }
+
+    #[test]
+    fn translates_an_earlier_presentation_timestamp_backward() {
+        let cpu = Instant::now() + Duration::from_secs(1);
+        let correlation = ClockCorrelation {
+            cpu,
+            presentation: wgpu::PresentationTimestamp(1_000),
+        };
+
+        let translated = correlation.to_cpu_instant(wgpu::PresentationTimestamp(500));
+
+        assert_eq!(translated, cpu - Duration::from_nanos(500));
+    }
+}
+
+/// A single presented frame's timing sample.
+///
+/// [`submitted_at`] is read from the CPU clock right before the frame is
+/// handed to the surface. [`presentation_timestamp`] is the reading of
+/// `wgpu`'s presentation clock recorded for that same frame, and
+/// [`latency`] is the delay between the two—computed at record time by
+/// translating `presentation_timestamp` back into CPU-clock terms via a
+/// [`ClockCorrelation`], since the two timestamps don't share a clock on
+/// their own.
+///
+/// [`submitted_at`]: Self::submitted_at
+/// [`presentation_timestamp`]: Self::presentation_timestamp
+/// [`latency`]: Self::latency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTiming {
+    /// The CPU instant at which the frame was submitted for presentation.
+    pub submitted_at: Instant,
+
+    /// The reading of `wgpu`'s presentation clock recorded for this frame.
+    pub presentation_timestamp: wgpu::PresentationTimestamp,
+
+    /// The delay between `submitted_at` and `presentation_timestamp`,
+    /// measured in CPU-clock terms.
+    pub latency: Duration,
+}
+
+/// A rolling window of recent [`FrameTiming`] samples, used to estimate
+/// frame intervals and presentation latency.
+///
+/// Enabled by [`Settings::capture_frame_timings`](crate::Settings::capture_frame_timings).
+#[derive(Debug, Clone)]
+pub struct FrameTimings {
+    samples: VecDeque<FrameTiming>,
+    capacity: usize,
+}
+
+impl FrameTimings {
+    /// Creates a new, empty [`FrameTimings`] window holding up to `capacity`
+    /// samples. Once full, recording a new sample drops the oldest one.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Records a new frame, computing its [`FrameTiming::latency`] by
+    /// translating `presentation_timestamp` into CPU-clock terms through
+    /// `correlation` and comparing it against `submitted_at`. Evicts the
+    /// oldest sample if the window is already at capacity.
+    pub fn record(
+        &mut self,
+        submitted_at: Instant,
+        presentation_timestamp: wgpu::PresentationTimestamp,
+        correlation: ClockCorrelation,
+    ) {
+        let presented_at = correlation.to_cpu_instant(presentation_timestamp);
+        let latency = presented_at.saturating_duration_since(submitted_at);
+
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(FrameTiming {
+            submitted_at,
+            presentation_timestamp,
+            latency,
+        });
+    }
+
+    /// Returns the CPU-side interval between each consecutive pair of
+    /// recorded frames, oldest first.
+    pub fn intervals(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|(previous, current)| {
+                current
+                    .submitted_at
+                    .saturating_duration_since(previous.submitted_at)
+            })
+    }
+
+    /// Returns the average of each recorded frame's [`FrameTiming::latency`]
+    /// across the current window, or `None` if no samples have been
+    /// recorded.
+    pub fn average_latency(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let total: Duration = self.samples.iter().map(|sample| sample.latency).sum();
+
+        Some(total / self.samples.len() as u32)
+    }
+
+    /// Returns the standard deviation of the recorded frame intervals, a
+    /// measure of frame pacing jitter. Returns `None` if fewer than two
+    /// intervals are available.
+    pub fn jitter(&self) -> Option<Duration> {
+        let intervals: Vec<f64> = self
+            .intervals()
+            .map(|interval| interval.as_secs_f64())
+            .collect();
+
+        if intervals.len() < 2 {
+            return None;
+        }
+
+        let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+        let variance = intervals
+            .iter()
+            .map(|interval| (interval - mean).powi(2))
+            .sum::<f64>()
+            / intervals.len() as f64;
+
+        Some(Duration::from_secs_f64(variance.sqrt()))
+    }
+
+    /// Returns the most recently recorded [`FrameTiming`], if any.
+    pub fn latest(&self) -> Option<&FrameTiming> {
+        self.samples.back()
+    }
+}
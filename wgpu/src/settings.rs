@@ -1,16 +1,23 @@
 //! Configure a renderer.
 use crate::core::{Font, Pixels};
 use crate::graphics::{self, Antialiasing};
+use crate::tonemapping::ToneMappingCurve;
 
 /// The settings of a [`Renderer`].
 ///
 /// [`Renderer`]: crate::Renderer
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Settings {
     /// The present mode of the [`Renderer`].
     ///
+    /// This can be a single, exact [`wgpu::PresentMode`] or a prioritized
+    /// [`PresentMode::Preference`] list. Use the latter when you have a
+    /// preferred mode (e.g. `Mailbox` for low latency) but want to fall
+    /// back gracefully on adapters/surfaces that don't support it, instead
+    /// of panicking.
+    ///
     /// [`Renderer`]: crate::Renderer
-    pub present_mode: wgpu::PresentMode,
+    pub present_mode: PresentMode,
 
     /// The preferred surface [`wgpu::TextureFormat`].
     ///
@@ -20,6 +27,25 @@ pub struct Settings {
     /// If `None`, the compositor selects a format automatically.
     pub format: Option<wgpu::TextureFormat>,
 
+    /// Additional [`wgpu::TextureFormat`]s the surface texture may be
+    /// reinterpreted as when creating a `wgpu::TextureView`.
+    ///
+    /// This mirrors `wgpu::SurfaceConfiguration::view_formats`. It lets the
+    /// compositor create, say, a `Bgra8Unorm` surface while still rendering
+    /// through a `Bgra8UnormSrgb` view (or vice versa), without a
+    /// reinterpreting copy—useful for correct gamma when mixing custom
+    /// shader passes with iced's own pipelines.
+    ///
+    /// Every format listed here must be validated against the resolved
+    /// surface format (`Rgba16Float` when [`Settings::hdr`] is in effect,
+    /// otherwise the supported [`Settings::format`] or the surface's first
+    /// format) with [`validate_view_formats`] before configuring it; each
+    /// entry must either equal the surface format or differ from it only in
+    /// its sRGB-ness.
+    ///
+    /// By default, it is empty.
+    pub view_formats: Vec<wgpu::TextureFormat>,
+
     /// The graphics backends to use.
     pub backends: wgpu::Backends,
 
@@ -35,29 +61,129 @@ pub struct Settings {
     ///
     /// By default, it is `None`.
     pub antialiasing: Option<Antialiasing>,
+
+    /// Whether the renderer should record [`FrameTiming`] samples for every
+    /// presented frame.
+    ///
+    /// When enabled, the compositor correlates each frame's CPU submit time
+    /// with the platform's presentation clock and keeps a rolling window of
+    /// the results, queryable through the renderer. This is useful for
+    /// adaptive quality decisions or an on-screen FPS/latency overlay.
+    ///
+    /// By default, it is `false`, since capturing timestamps has a (small)
+    /// runtime cost.
+    ///
+    /// [`FrameTiming`]: crate::frame_timing::FrameTiming
+    pub capture_frame_timings: bool,
+
+    /// Enables an extended-range (HDR/EDR) output pipeline.
+    ///
+    /// When `Some`, the compositor configures the surface with an
+    /// `Rgba16Float` format (falling back to SDR if the surface doesn't
+    /// support it) and runs [`tonemapping::Pipeline`] as a final pass that
+    /// applies the configured [`HdrSettings::tone_mapping`] curve, so SDR
+    /// content authored in the `0..1` range still maps correctly onto the
+    /// display's peak luminance.
+    ///
+    /// By default, it is `None`. Setting `ICED_FORMAT=rgba16float` enables
+    /// it with [`HdrSettings::default`] via [`hdr_from_env`].
+    ///
+    /// [`tonemapping::Pipeline`]: crate::tonemapping::Pipeline
+    pub hdr: Option<HdrSettings>,
 }
 
 impl Default for Settings {
     fn default() -> Settings {
         Settings {
-            present_mode: wgpu::PresentMode::AutoVsync,
+            present_mode: PresentMode::Exact(wgpu::PresentMode::AutoVsync),
             format: None,
+            view_formats: Vec::new(),
             backends: wgpu::Backends::all(),
             default_font: Font::default(),
             default_text_size: Pixels(16.0),
             antialiasing: None,
+            capture_frame_timings: false,
+            hdr: None,
+        }
+    }
+}
+
+/// Configuration for the extended-range (HDR/EDR) output pipeline enabled by
+/// [`Settings::hdr`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HdrSettings {
+    /// The color space the extended-range surface is tagged with.
+    ///
+    /// The compositor does not read this value; see [`ColorSpace`].
+    pub color_space: ColorSpace,
+
+    /// The tone-mapping curve applied to HDR output before presentation.
+    pub tone_mapping: ToneMappingCurve,
+
+    /// The luminance, in nits, that SDR white (`1.0`) is mapped to.
+    /// `tone_mapping` pins SDR white to this value, so content authored in
+    /// the `0..1` range reaches exactly `peak_luminance` nits on the display.
+    /// Highlights above `1.0` are mapped above this value (approaching twice
+    /// it with [`ToneMappingCurve::Reinhard`], up to about `1.24` times it
+    /// with [`ToneMappingCurve::AcesFilmic`]); only
+    /// [`ToneMappingCurve::None`] clamps at it.
+    ///
+    /// By default, it is `203.0`—the reference SDR white level defined by
+    /// ITU-R BT.2100.
+    pub peak_luminance: f32,
+}
+
+impl Default for HdrSettings {
+    fn default() -> Self {
+        Self {
+            color_space: ColorSpace::LinearExtendedSrgb,
+            tone_mapping: ToneMappingCurve::default(),
+            peak_luminance: 203.0,
         }
     }
 }
 
+/// The color space an extended-range surface is presented in.
+///
+/// The compositor does not apply the requested space to the surface
+/// configuration and does not check whether it is available: its fallback
+/// to SDR depends only on whether the surface supports `Rgba16Float`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    /// Standard, display-referred sRGB.
+    #[default]
+    Srgb,
+
+    /// Linear, scene-referred values extended beyond `0..1`, as used by
+    /// `scRGB` on Windows and `extended linear sRGB` on macOS.
+    LinearExtendedSrgb,
+
+    /// The wide-gamut Display P3 color space.
+    DisplayP3,
+}
+
+/// Obtains [`HdrSettings`] from the current environment configuration, if
+/// HDR should be enabled.
+///
+/// This enables HDR with sensible defaults whenever [`format_from_env`]
+/// resolves to [`wgpu::TextureFormat::Rgba16Float`] (i.e. `ICED_FORMAT=rgba16float`),
+/// so that shortcut alone is enough to light up the tone-mapping pipeline
+/// without additional configuration.
+pub fn hdr_from_env() -> Option<HdrSettings> {
+    match format_from_env()? {
+        wgpu::TextureFormat::Rgba16Float => Some(HdrSettings::default()),
+        _ => None,
+    }
+}
+
 impl From<graphics::Settings> for Settings {
     fn from(settings: graphics::Settings) -> Self {
         Self {
-            present_mode: if settings.vsync {
+            present_mode: PresentMode::Exact(if settings.vsync {
                 wgpu::PresentMode::AutoVsync
             } else {
                 wgpu::PresentMode::AutoNoVsync
-            },
+            }),
             default_font: settings.default_font,
             default_text_size: settings.default_text_size,
             antialiasing: settings.antialiasing,
@@ -66,6 +192,199 @@ impl From<graphics::Settings> for Settings {
     }
 }
 
+/// The present mode requested by [`Settings::present_mode`].
+///
+/// A [`PresentMode::Preference`] is resolved against the modes a surface
+/// actually supports by [`negotiate_present_mode`], which is what the
+/// compositor calls before configuring the surface. This avoids the panic
+/// (or undefined behavior, depending on the backend) that comes from
+/// handing wgpu a present mode the adapter/surface doesn't support.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresentMode {
+    /// Use this exact present mode, falling back to [`wgpu::PresentMode::Fifo`]
+    /// if the surface doesn't support it.
+    Exact(wgpu::PresentMode),
+
+    /// Try each mode in order, using the first one the surface supports and
+    /// falling back to [`wgpu::PresentMode::Fifo`] if none of them are.
+    Preference(Vec<wgpu::PresentMode>),
+}
+
+impl PresentMode {
+    /// Returns the modes to try, in priority order.
+    pub fn preferences(&self) -> &[wgpu::PresentMode] {
+        match self {
+            Self::Exact(mode) => std::slice::from_ref(mode),
+            Self::Preference(modes) => modes,
+        }
+    }
+}
+
+impl From<wgpu::PresentMode> for PresentMode {
+    fn from(mode: wgpu::PresentMode) -> Self {
+        Self::Exact(mode)
+    }
+}
+
+impl Default for PresentMode {
+    fn default() -> Self {
+        Self::Exact(wgpu::PresentMode::AutoVsync)
+    }
+}
+
+/// Resolves a requested [`PresentMode`] against the present modes a surface
+/// actually supports (e.g. from `wgpu::Surface::get_capabilities`), falling
+/// back to [`wgpu::PresentMode::Fifo`]—which every surface is required to
+/// support—if none of the requested modes are available.
+///
+/// [`wgpu::PresentMode::AutoVsync`] and [`wgpu::PresentMode::AutoNoVsync`]
+/// are meta-modes resolved internally by wgpu and, unlike every other
+/// variant, never appear in `supported`—so they are passed through
+/// untouched instead of being matched against it. Only concrete modes
+/// (`Immediate`, `Mailbox`, `Fifo`, `FifoRelaxed`) are negotiated.
+///
+/// The returned mode should be surfaced back to the caller (e.g. stored
+/// alongside the renderer) so applications can display what present mode
+/// they actually got, rather than assuming they received what they asked
+/// for.
+pub fn negotiate_present_mode(
+    requested: &PresentMode,
+    supported: &[wgpu::PresentMode],
+) -> wgpu::PresentMode {
+    requested
+        .preferences()
+        .iter()
+        .copied()
+        .find(|mode| is_auto_present_mode(*mode) || supported.contains(mode))
+        .unwrap_or(wgpu::PresentMode::Fifo)
+}
+
+fn is_auto_present_mode(mode: wgpu::PresentMode) -> bool {
+    matches!(
+        mode,
+        wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync
+    )
+}
+
+#[cfg(test)]
+mod negotiate_present_mode_tests {
+    use super::*;
+
+    #[test]
+    fn passes_auto_modes_through_untouched() {
+        let supported = [wgpu::PresentMode::Fifo, wgpu::PresentMode::Mailbox];
+
+        assert_eq!(
+            negotiate_present_mode(
+                &PresentMode::Exact(wgpu::PresentMode::AutoVsync),
+                &supported
+            ),
+            wgpu::PresentMode::AutoVsync
+        );
+    }
+
+    #[test]
+    fn picks_first_supported_preference() {
+        let supported = [wgpu::PresentMode::Fifo];
+
+        assert_eq!(
+            negotiate_present_mode(
+                &PresentMode::Preference(vec![
+                    wgpu::PresentMode::Mailbox,
+                    wgpu::PresentMode::Fifo,
+                ]),
+                &supported
+            ),
+            wgpu::PresentMode::Fifo
+        );
+    }
+
+    #[test]
+    fn falls_back_to_fifo_when_nothing_matches() {
+        let supported = [wgpu::PresentMode::Mailbox];
+
+        assert_eq!(
+            negotiate_present_mode(
+                &PresentMode::Exact(wgpu::PresentMode::Immediate),
+                &supported
+            ),
+            wgpu::PresentMode::Fifo
+        );
+    }
+}
+
+/// An error produced when a [`Settings::view_formats`] entry is not
+/// compatible with the surface format it would be paired with.
+///
+/// wgpu only allows a view format that is either identical to the surface
+/// format or differs from it only in its sRGB-ness (e.g. `Bgra8Unorm` and
+/// `Bgra8UnormSrgb`); anything else will panic deep inside the graphics
+/// backend, so [`validate_view_formats`] catches it up front.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncompatibleViewFormat {
+    /// The surface format the view format was validated against.
+    pub format: wgpu::TextureFormat,
+    /// The view format that is not compatible with `format`.
+    pub view_format: wgpu::TextureFormat,
+}
+
+impl std::fmt::Display for IncompatibleViewFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "view format {:?} is not compatible with surface format {:?}; \
+             it must be identical or differ only in sRGB-ness",
+            self.view_format, self.format
+        )
+    }
+}
+
+impl std::error::Error for IncompatibleViewFormat {}
+
+/// Validates that every `view_format` can be used as a `wgpu::TextureView`
+/// format for a surface configured with `format`.
+pub fn validate_view_formats(
+    format: wgpu::TextureFormat,
+    view_formats: &[wgpu::TextureFormat],
+) -> Result<(), IncompatibleViewFormat> {
+    for &view_format in view_formats {
+        let compatible = view_format == format
+            || view_format.remove_srgb_suffix() == format.remove_srgb_suffix();
+
+        if !compatible {
+            return Err(IncompatibleViewFormat { format, view_format });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod validate_view_formats_tests {
+    use super::*;
+
+    #[test]
+    fn accepts_srgb_variant_of_the_surface_format() {
+        assert!(validate_view_formats(
+            wgpu::TextureFormat::Bgra8Unorm,
+            &[wgpu::TextureFormat::Bgra8UnormSrgb],
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn rejects_an_unrelated_format() {
+        let error = validate_view_formats(
+            wgpu::TextureFormat::Bgra8Unorm,
+            &[wgpu::TextureFormat::Rgba8Unorm],
+        )
+        .unwrap_err();
+
+        assert_eq!(error.format, wgpu::TextureFormat::Bgra8Unorm);
+        assert_eq!(error.view_format, wgpu::TextureFormat::Rgba8Unorm);
+    }
+}
+
 /// Obtains a [`wgpu::TextureFormat`] from the current environment
 /// configuration, if set.
 ///
@@ -115,3 +434,195 @@ pub fn present_mode_from_env() -> Option<wgpu::PresentMode> {
         _ => None,
     }
 }
+
+/// A builder for [`Settings`] that defers picking a surface format and
+/// present mode until an adapter and surface are available, filling in
+/// unspecified fields with values the surface actually supports and
+/// rejecting an unsupported explicit format with a descriptive error—instead
+/// of letting wgpu panic deep inside surface configuration.
+///
+/// An explicitly requested present mode is not rejected: it goes through
+/// [`negotiate_present_mode`] like any other, so an unsupported one falls
+/// back to [`wgpu::PresentMode::Fifo`] without an error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsBuilder {
+    present_mode: Option<PresentMode>,
+    format: Option<wgpu::TextureFormat>,
+    view_formats: Vec<wgpu::TextureFormat>,
+    backends: Option<wgpu::Backends>,
+    default_font: Font,
+    default_text_size: Pixels,
+    antialiasing: Option<Antialiasing>,
+    capture_frame_timings: bool,
+    hdr: Option<HdrSettings>,
+}
+
+impl Default for SettingsBuilder {
+    // Seeded from `Settings::default()`, not derived field-wise, so
+    // `default_text_size` stays `16.0` instead of drifting to `0.0`.
+    fn default() -> Self {
+        let defaults = Settings::default();
+
+        Self {
+            present_mode: None,
+            format: None,
+            view_formats: Vec::new(),
+            backends: None,
+            default_font: defaults.default_font,
+            default_text_size: defaults.default_text_size,
+            antialiasing: defaults.antialiasing,
+            capture_frame_timings: defaults.capture_frame_timings,
+            hdr: defaults.hdr,
+        }
+    }
+}
+
+impl SettingsBuilder {
+    /// Creates a new [`SettingsBuilder`] with nothing configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests vsync, equivalent to [`PresentMode::Exact`] with
+    /// [`wgpu::PresentMode::AutoVsync`] or [`wgpu::PresentMode::AutoNoVsync`].
+    pub fn vsync(mut self, enabled: bool) -> Self {
+        self.present_mode = Some(PresentMode::Exact(if enabled {
+            wgpu::PresentMode::AutoVsync
+        } else {
+            wgpu::PresentMode::AutoNoVsync
+        }));
+        self
+    }
+
+    /// Sets the requested [`PresentMode`], accepting either a single
+    /// [`wgpu::PresentMode`] or a [`PresentMode::Preference`] list.
+    pub fn present_mode(mut self, present_mode: impl Into<PresentMode>) -> Self {
+        self.present_mode = Some(present_mode.into());
+        self
+    }
+
+    /// Sets the preferred surface [`wgpu::TextureFormat`].
+    pub fn prefer_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Sets the additional [`Settings::view_formats`] the surface texture
+    /// may be reinterpreted as.
+    pub fn view_formats(mut self, view_formats: Vec<wgpu::TextureFormat>) -> Self {
+        self.view_formats = view_formats;
+        self
+    }
+
+    /// Sets the graphics backends to use.
+    pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = Some(backends);
+        self
+    }
+
+    /// Sets the antialiasing strategy to use for triangle primitives.
+    pub fn antialiasing(mut self, antialiasing: impl Into<Option<Antialiasing>>) -> Self {
+        self.antialiasing = antialiasing.into();
+        self
+    }
+
+    /// Enables capturing [`FrameTiming`](crate::frame_timing::FrameTiming)
+    /// samples, as described in [`Settings::capture_frame_timings`].
+    pub fn capture_frame_timings(mut self, enabled: bool) -> Self {
+        self.capture_frame_timings = enabled;
+        self
+    }
+
+    /// Enables the HDR/EDR output pipeline, as described in [`Settings::hdr`].
+    pub fn hdr(mut self, hdr: impl Into<Option<HdrSettings>>) -> Self {
+        self.hdr = hdr.into();
+        self
+    }
+
+    /// Fills in any field that hasn't been explicitly set yet from the
+    /// current environment, using [`format_from_env`], [`present_mode_from_env`],
+    /// and [`hdr_from_env`].
+    pub fn from_env(mut self) -> Self {
+        self.format = self.format.or_else(format_from_env);
+        self.present_mode = self
+            .present_mode
+            .or_else(|| present_mode_from_env().map(PresentMode::Exact));
+        self.hdr = self.hdr.or_else(hdr_from_env);
+        self
+    }
+
+    /// Resolves this builder into a concrete [`Settings`] by querying the
+    /// present modes and formats `surface` actually supports on `adapter`
+    /// (via `wgpu::Surface::get_capabilities`), filling in unspecified
+    /// fields, validating an explicit format and view formats, and
+    /// negotiating the present mode with [`negotiate_present_mode`].
+    pub fn build(
+        self,
+        adapter: &wgpu::Adapter,
+        surface: &wgpu::Surface<'_>,
+    ) -> Result<Settings, BuildSettingsError> {
+        let capabilities = surface.get_capabilities(adapter);
+
+        let format = match self.format {
+            Some(format) if capabilities.formats.contains(&format) => format,
+            Some(format) => return Err(BuildSettingsError::UnsupportedFormat(format)),
+            None => capabilities
+                .formats
+                .first()
+                .copied()
+                .ok_or(BuildSettingsError::NoSupportedFormat)?,
+        };
+
+        validate_view_formats(format, &self.view_formats)
+            .map_err(BuildSettingsError::IncompatibleViewFormat)?;
+
+        let present_mode = negotiate_present_mode(
+            &self.present_mode.unwrap_or_default(),
+            &capabilities.present_modes,
+        );
+
+        Ok(Settings {
+            present_mode: PresentMode::Exact(present_mode),
+            format: Some(format),
+            view_formats: self.view_formats,
+            backends: self.backends.unwrap_or_else(wgpu::Backends::all),
+            default_font: self.default_font,
+            default_text_size: self.default_text_size,
+            antialiasing: self.antialiasing,
+            capture_frame_timings: self.capture_frame_timings,
+            hdr: self.hdr,
+        })
+    }
+}
+
+/// An error produced while resolving a [`SettingsBuilder`] against an
+/// adapter and surface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildSettingsError {
+    /// The explicitly requested [`Settings::format`] isn't supported by the
+    /// surface.
+    UnsupportedFormat(wgpu::TextureFormat),
+
+    /// The surface doesn't support any format at all.
+    NoSupportedFormat,
+
+    /// A requested [`Settings::view_formats`] entry is incompatible with the
+    /// resolved surface format.
+    IncompatibleViewFormat(IncompatibleViewFormat),
+}
+
+impl std::fmt::Display for BuildSettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedFormat(format) => {
+                write!(f, "surface does not support requested format {format:?}")
+            }
+            Self::NoSupportedFormat => {
+                write!(f, "surface does not support any texture format")
+            }
+            Self::IncompatibleViewFormat(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for BuildSettingsError {}
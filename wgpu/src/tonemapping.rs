@@ -0,0 +1,263 @@
+//! Tone map HDR color values down to a display's supported range.
+
+/// A tone-mapping curve applied to HDR output before it reaches the
+/// display, as configured by [`HdrSettings::tone_mapping`].
+///
+/// [`HdrSettings::tone_mapping`]: crate::settings::HdrSettings::tone_mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMappingCurve {
+    /// No tone mapping; values are clamped to the peak luminance as-is.
+    None,
+
+    /// The simple Reinhard curve, `c = x / (1 + x)`, applied per channel.
+    ///
+    /// Cheap and monotonic, but desaturates highlights more aggressively
+    /// than a filmic curve.
+    #[default]
+    Reinhard,
+
+    /// An ACES-style filmic approximation:
+    /// `(x * (a * x + b)) / (x * (c * x + d) + e)`, with the standard
+    /// constants `a = 2.51`, `b = 0.03`, `c = 2.43`, `d = 0.59`, `e = 0.14`.
+    ///
+    /// Closer to how film stock rolls off highlights; the usual choice for
+    /// content that should look natural rather than clipped.
+    AcesFilmic,
+}
+
+impl ToneMappingCurve {
+    fn curve(self, x: f32) -> f32 {
+        match self {
+            Self::None => x.clamp(0.0, 1.0),
+            Self::Reinhard => reinhard(x),
+            Self::AcesFilmic => aces_filmic(x),
+        }
+    }
+
+    /// Applies the curve to a single linear color channel authored in the
+    /// conventional `0..1` SDR range (values above `1.0` represent HDR
+    /// highlights), normalizing so SDR white (`1.0`) lands exactly at
+    /// `peak_luminance` nits—matching [`HdrSettings::peak_luminance`]—with
+    /// highlights above it compressed and scaled accordingly.
+    ///
+    /// [`HdrSettings::peak_luminance`]: crate::settings::HdrSettings::peak_luminance
+    pub fn apply(self, value: f32, peak_luminance: f32) -> f32 {
+        let white = self.curve(1.0);
+        let normalized = if white > 0.0 {
+            self.curve(value) / white
+        } else {
+            self.curve(value)
+        };
+
+        normalized * peak_luminance
+    }
+}
+
+/// The Reinhard tone-mapping operator, `c = x / (1 + x)`.
+pub fn reinhard(x: f32) -> f32 {
+    x / (1.0 + x)
+}
+
+/// An ACES-style filmic tone-mapping approximation, using the standard
+/// constants popularized by Krzysztof Narkowicz.
+pub fn aces_filmic(x: f32) -> f32 {
+    const A: f32 = 2.51;
+    const B: f32 = 0.03;
+    const C: f32 = 2.43;
+    const D: f32 = 0.59;
+    const E: f32 = 0.14;
+
+    ((x * (A * x + B)) / (x * (C * x + D) + E)).clamp(0.0, 1.0)
+}
+
+impl ToneMappingCurve {
+    fn shader_index(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Reinhard => 1,
+            Self::AcesFilmic => 2,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Uniforms {
+    curve: u32,
+    peak_luminance: f32,
+    // `wgpu` requires uniform buffer bindings to be 16-byte aligned.
+    _padding: [u32; 2],
+}
+
+/// The final compositor pass applied when
+/// [`Settings::hdr`](crate::Settings::hdr) is enabled: a full-screen pass
+/// that samples the HDR color target produced by the rest of the pipeline
+/// and writes out the tone-mapped result using [`HdrSettings::tone_mapping`]
+/// and [`HdrSettings::peak_luminance`].
+///
+/// [`HdrSettings::tone_mapping`]: crate::settings::HdrSettings::tone_mapping
+/// [`HdrSettings::peak_luminance`]: crate::settings::HdrSettings::peak_luminance
+#[derive(Debug)]
+pub struct Pipeline {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniforms: wgpu::Buffer,
+}
+
+impl Pipeline {
+    /// Creates the tone-mapping pipeline, targeting `target_format` (the
+    /// surface's configured format).
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("shader/tonemap.wgsl"));
+
+        let bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("iced_wgpu::tonemapping bind group layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("iced_wgpu::tonemapping pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("iced_wgpu::tonemapping pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("iced_wgpu::tonemapping sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..wgpu::SamplerDescriptor::default()
+        });
+
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("iced_wgpu::tonemapping uniforms"),
+            size: std::mem::size_of::<Uniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniforms,
+        }
+    }
+
+    /// Uploads the curve and peak luminance from `settings` for the next
+    /// call to [`Pipeline::render`].
+    pub fn update(&self, queue: &wgpu::Queue, settings: crate::settings::HdrSettings) {
+        queue.write_buffer(
+            &self.uniforms,
+            0,
+            bytemuck::bytes_of(&Uniforms {
+                curve: settings.tone_mapping.shader_index(),
+                peak_luminance: settings.peak_luminance,
+                _padding: [0; 2],
+            }),
+        );
+    }
+
+    /// Runs the tone-mapping pass, reading from `hdr_view` (the extended
+    /// range color target the rest of the compositor rendered into) and
+    /// writing the tone-mapped result to `target`.
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        hdr_view: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+    ) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("iced_wgpu::tonemapping bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(hdr_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.uniforms.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("iced_wgpu::tonemapping pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,219 @@
+//! Configure a `wgpu::Surface` from [`Settings`] and present frames to it.
+use crate::frame_timing::{ClockCorrelation, FrameTimings};
+use crate::settings::{self, HdrSettings, IncompatibleViewFormat, Settings};
+use crate::tonemapping;
+
+use std::time::Instant;
+
+/// The number of recent frames kept in [`Compositor::frame_timings`]'s
+/// rolling window.
+const FRAME_TIMING_WINDOW: usize = 120;
+
+/// Owns the `wgpu` resources needed to configure a surface from [`Settings`]
+/// and present frames to it.
+///
+/// This is where every [`Settings`] field is actually threaded into surface
+/// configuration: [`Settings::present_mode`] is resolved against what the
+/// surface supports with [`settings::negotiate_present_mode`],
+/// [`Settings::view_formats`] is checked with [`settings::validate_view_formats`],
+/// [`Settings::hdr`] runs [`tonemapping::Pipeline`] as a final pass before
+/// presentation, and, when [`Settings::capture_frame_timings`] is enabled,
+/// every presented frame is recorded into a [`FrameTimings`] window.
+#[derive(Debug)]
+pub struct Compositor {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    adapter: wgpu::Adapter,
+    config: wgpu::SurfaceConfiguration,
+    present_mode: wgpu::PresentMode,
+    frame_timings: Option<FrameTimings>,
+    clock_correlation: Option<ClockCorrelation>,
+    hdr: Option<HdrSettings>,
+    hdr_target: Option<wgpu::Texture>,
+    tonemapping: Option<tonemapping::Pipeline>,
+}
+
+impl Compositor {
+    /// Configures `surface` for presentation on `adapter`, resolving
+    /// [`Settings::present_mode`] and [`Settings::format`] against what the
+    /// surface actually supports and validating [`Settings::view_formats`]
+    /// against the resolved format.
+    pub fn configure(
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        adapter: wgpu::Adapter,
+        surface: &wgpu::Surface<'_>,
+        settings: &Settings,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, IncompatibleViewFormat> {
+        let capabilities = surface.get_capabilities(&adapter);
+
+        let sdr_format = settings
+            .format
+            .filter(|format| capabilities.formats.contains(format))
+            .or_else(|| capabilities.formats.first().copied())
+            .unwrap_or(wgpu::TextureFormat::Bgra8UnormSrgb);
+
+        let hdr_supported = settings.hdr.is_some()
+            && capabilities
+                .formats
+                .contains(&wgpu::TextureFormat::Rgba16Float);
+
+        let format = if hdr_supported {
+            wgpu::TextureFormat::Rgba16Float
+        } else {
+            sdr_format
+        };
+
+        settings::validate_view_formats(format, &settings.view_formats)?;
+
+        let present_mode =
+            settings::negotiate_present_mode(&settings.present_mode, &capabilities.present_modes);
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width,
+            height,
+            present_mode,
+            alpha_mode: capabilities.alpha_modes[0],
+            view_formats: settings.view_formats.clone(),
+            desired_maximum_frame_latency: 2,
+        };
+
+        surface.configure(&device, &config);
+
+        let (hdr_target, tonemapping_pipeline) = if hdr_supported {
+            (
+                Some(Self::create_hdr_target(&device, format, width, height)),
+                Some(tonemapping::Pipeline::new(&device, format)),
+            )
+        } else {
+            (None, None)
+        };
+
+        let clock_correlation = settings
+            .capture_frame_timings
+            .then(|| ClockCorrelation::capture(&adapter));
+
+        Ok(Self {
+            device,
+            queue,
+            adapter,
+            config,
+            present_mode,
+            frame_timings: settings
+                .capture_frame_timings
+                .then(|| FrameTimings::new(FRAME_TIMING_WINDOW)),
+            clock_correlation,
+            hdr: hdr_supported.then_some(settings.hdr).flatten(),
+            hdr_target,
+            tonemapping: tonemapping_pipeline,
+        })
+    }
+
+    fn create_hdr_target(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("iced_wgpu::tonemapping hdr target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+    }
+
+    /// The present mode actually negotiated with the surface; may differ
+    /// from what [`Settings::present_mode`] requested.
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.present_mode
+    }
+
+    /// The surface format this compositor was configured with.
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.config.format
+    }
+
+    /// The rolling [`FrameTimings`] window, if [`Settings::capture_frame_timings`]
+    /// was enabled.
+    pub fn frame_timings(&self) -> Option<&FrameTimings> {
+        self.frame_timings.as_ref()
+    }
+
+    /// The view the rest of the renderer should draw `frame` into.
+    ///
+    /// When HDR is enabled this is an offscreen extended-range target,
+    /// which [`Compositor::present`] tone maps onto `frame` as a final
+    /// pass; otherwise it's a view of `frame` itself.
+    pub fn target_view(&self, frame: &wgpu::SurfaceTexture) -> wgpu::TextureView {
+        match &self.hdr_target {
+            Some(texture) => texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            None => frame
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default()),
+        }
+    }
+
+    /// Presents `frame`, running the [`tonemapping::Pipeline`] pass first if
+    /// HDR is enabled, and recording a
+    /// [`FrameTiming`](crate::frame_timing::FrameTiming) sample if frame
+    /// timing capture is enabled.
+    ///
+    /// The sample's presentation timestamp is the adapter's presentation
+    /// clock read right after `frame.present()` returns, with the
+    /// [`ClockCorrelation`] recaptured for every frame. Its latency therefore
+    /// covers the tone-mapping submit and the `present` call; it is not the
+    /// moment the frame reaches the display.
+    pub fn present(&mut self, frame: wgpu::SurfaceTexture) {
+        let submitted_at = Instant::now();
+
+        if let (Some(hdr), Some(pipeline), Some(hdr_target)) =
+            (self.hdr, &self.tonemapping, &self.hdr_target)
+        {
+            pipeline.update(&self.queue, hdr);
+
+            let hdr_view = hdr_target.create_view(&wgpu::TextureViewDescriptor::default());
+            let target_view = frame
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+
+            let mut encoder =
+                self.device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("iced_wgpu::tonemapping encoder"),
+                    });
+
+            pipeline.render(&self.device, &mut encoder, &hdr_view, &target_view);
+
+            self.queue.submit(Some(encoder.finish()));
+        }
+
+        frame.present();
+
+        if self.frame_timings.is_some() {
+            self.clock_correlation = Some(ClockCorrelation::capture(&self.adapter));
+        }
+
+        if let Some(correlation) = self.clock_correlation {
+            if let Some(frame_timings) = &mut self.frame_timings {
+                frame_timings.record(
+                    submitted_at,
+                    self.adapter.get_presentation_timestamp(),
+                    correlation,
+                );
+            }
+        }
+    }
+}
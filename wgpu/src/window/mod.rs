@@ -0,0 +1,4 @@
+//! Configure and manage a window's surface.
+pub mod compositor;
+
+pub use compositor::Compositor;